@@ -0,0 +1,26 @@
+use std::fmt;
+
+/// Generic, subsystem-agnostic error produced by shared helpers like
+/// [`crate::handler::find_model_by_full_name`]. Callers are expected to
+/// convert it into their own subsystem's error type (e.g.
+/// `EmbeddingError`'s `From<GatewayApiError>` impl) rather than return it
+/// directly from a handler, so it intentionally doesn't implement
+/// `actix_web::ResponseError` itself.
+#[derive(Debug)]
+pub enum GatewayApiError {
+    BadRequest(String),
+    NotFound(String),
+    Internal(String),
+}
+
+impl fmt::Display for GatewayApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GatewayApiError::BadRequest(msg) => write!(f, "bad request: {msg}"),
+            GatewayApiError::NotFound(msg) => write!(f, "not found: {msg}"),
+            GatewayApiError::Internal(msg) => write!(f, "internal error: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for GatewayApiError {}
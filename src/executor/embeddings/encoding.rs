@@ -0,0 +1,76 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+
+use crate::types::gateway::{EmbeddingVector, EncodingFormat};
+
+use super::EmbeddingError;
+
+/// Truncates `embedding` to `dimensions` (Matryoshka-style) and re-normalizes
+/// it to unit length so similarity comparisons against the truncated vector
+/// remain valid. A `None` request leaves the native vector untouched.
+pub fn apply_dimensions(
+    embedding: Vec<f32>,
+    dimensions: Option<usize>,
+) -> Result<Vec<f32>, EmbeddingError> {
+    let Some(dimensions) = dimensions else {
+        return Ok(embedding);
+    };
+
+    if dimensions > embedding.len() {
+        return Err(EmbeddingError::InvalidDimensions(format!(
+            "requested {dimensions} dimensions but the model's native output is only {} dimensions",
+            embedding.len()
+        )));
+    }
+
+    let mut truncated = embedding;
+    truncated.truncate(dimensions);
+    let norm = truncated.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for x in &mut truncated {
+            *x /= norm;
+        }
+    }
+    Ok(truncated)
+}
+
+/// Encodes `embedding` in whichever representation the client asked for.
+/// `base64` packs each component as a little-endian `f32` to cut payload
+/// size for large batches.
+pub fn encode(embedding: Vec<f32>, format: EncodingFormat) -> EmbeddingVector {
+    match format {
+        EncodingFormat::Float => EmbeddingVector::Float(embedding),
+        EncodingFormat::Base64 => {
+            let mut bytes = Vec::with_capacity(embedding.len() * 4);
+            for value in embedding {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            EmbeddingVector::Base64(BASE64.encode(bytes))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dimensions_equal_to_native_renormalizes_in_place() {
+        let out = apply_dimensions(vec![3.0, 4.0], Some(2)).unwrap();
+        assert_eq!(out.len(), 2);
+        let norm: f32 = out.iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn dimensions_larger_than_native_is_rejected() {
+        let err = apply_dimensions(vec![1.0, 0.0], Some(3)).unwrap_err();
+        assert!(matches!(err, EmbeddingError::InvalidDimensions(_)));
+    }
+
+    #[test]
+    fn dimensions_of_zero_yields_an_empty_vector() {
+        let out = apply_dimensions(vec![1.0, 2.0, 3.0], Some(0)).unwrap();
+        assert!(out.is_empty());
+    }
+}
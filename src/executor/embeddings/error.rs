@@ -0,0 +1,154 @@
+use std::fmt;
+use std::time::Duration;
+
+use actix_web::http::StatusCode;
+use actix_web::{HttpResponse, ResponseError};
+use serde::Serialize;
+
+use crate::GatewayApiError;
+
+/// Who is responsible for an embedding failure, used to decide both the
+/// HTTP status code and whether a caller should retry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultSource {
+    /// The request itself was malformed or unsatisfiable (bad input,
+    /// unknown model, invalid dimensions). Retrying unchanged will not help.
+    User,
+    /// The upstream provider failed, rejected the request, or is rate
+    /// limiting us. Safe to retry, possibly after a delay.
+    Provider,
+    /// Something went wrong on our side while serving the request
+    /// (tokenization, tensor shape/value errors in the local provider).
+    Runtime,
+}
+
+/// Errors raised anywhere in the embeddings subsystem, tagged with a
+/// [`FaultSource`] so handlers can map them to the right HTTP status and
+/// callers can tell programmatically whether a retry is worthwhile.
+#[derive(Debug)]
+pub enum EmbeddingError {
+    InvalidInput(String),
+    ModelNotFound(String),
+    InvalidDimensions(String),
+    CollectionNotFound(String),
+    DimensionMismatch(String),
+    ProviderUnavailable(String),
+    ProviderResponse(String),
+    ProviderAuth(String),
+    RateLimited { message: String, retry_after: Option<Duration> },
+    Tokenization(String),
+    Tensor(String),
+}
+
+impl EmbeddingError {
+    pub fn fault_source(&self) -> FaultSource {
+        match self {
+            EmbeddingError::InvalidInput(_)
+            | EmbeddingError::ModelNotFound(_)
+            | EmbeddingError::InvalidDimensions(_)
+            | EmbeddingError::CollectionNotFound(_)
+            | EmbeddingError::DimensionMismatch(_) => FaultSource::User,
+            EmbeddingError::ProviderUnavailable(_)
+            | EmbeddingError::ProviderResponse(_)
+            | EmbeddingError::ProviderAuth(_)
+            | EmbeddingError::RateLimited { .. } => FaultSource::Provider,
+            EmbeddingError::Tokenization(_) | EmbeddingError::Tensor(_) => FaultSource::Runtime,
+        }
+    }
+
+    /// A stable, machine-readable identifier clients can match on, separate
+    /// from the human-readable message (which may change wording freely).
+    pub fn code(&self) -> &'static str {
+        match self {
+            EmbeddingError::InvalidInput(_) => "invalid_input",
+            EmbeddingError::ModelNotFound(_) => "model_not_found",
+            EmbeddingError::InvalidDimensions(_) => "invalid_dimensions",
+            EmbeddingError::CollectionNotFound(_) => "collection_not_found",
+            EmbeddingError::DimensionMismatch(_) => "dimension_mismatch",
+            EmbeddingError::ProviderUnavailable(_) => "provider_unavailable",
+            EmbeddingError::ProviderResponse(_) => "provider_response_error",
+            EmbeddingError::ProviderAuth(_) => "provider_auth_error",
+            EmbeddingError::RateLimited { .. } => "provider_rate_limited",
+            EmbeddingError::Tokenization(_) => "tokenization_error",
+            EmbeddingError::Tensor(_) => "tensor_error",
+        }
+    }
+}
+
+impl fmt::Display for EmbeddingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmbeddingError::InvalidInput(msg)
+            | EmbeddingError::ModelNotFound(msg)
+            | EmbeddingError::InvalidDimensions(msg)
+            | EmbeddingError::CollectionNotFound(msg)
+            | EmbeddingError::DimensionMismatch(msg)
+            | EmbeddingError::ProviderUnavailable(msg)
+            | EmbeddingError::ProviderResponse(msg)
+            | EmbeddingError::ProviderAuth(msg)
+            | EmbeddingError::Tokenization(msg)
+            | EmbeddingError::Tensor(msg) => write!(f, "{msg}"),
+            EmbeddingError::RateLimited { message, .. } => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for EmbeddingError {}
+
+impl From<GatewayApiError> for EmbeddingError {
+    fn from(err: GatewayApiError) -> Self {
+        match err {
+            GatewayApiError::BadRequest(msg) => EmbeddingError::InvalidInput(msg),
+            GatewayApiError::NotFound(msg) => EmbeddingError::ModelNotFound(msg),
+            GatewayApiError::Internal(msg) => EmbeddingError::Tensor(msg),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: ErrorBodyInner,
+}
+
+#[derive(Serialize)]
+struct ErrorBodyInner {
+    code: &'static str,
+    message: String,
+}
+
+impl ResponseError for EmbeddingError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            EmbeddingError::InvalidInput(_)
+            | EmbeddingError::InvalidDimensions(_)
+            | EmbeddingError::DimensionMismatch(_) => StatusCode::BAD_REQUEST,
+            EmbeddingError::ModelNotFound(_) | EmbeddingError::CollectionNotFound(_) => {
+                StatusCode::NOT_FOUND
+            }
+            EmbeddingError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            EmbeddingError::ProviderAuth(_) => StatusCode::BAD_GATEWAY,
+            EmbeddingError::ProviderUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            EmbeddingError::ProviderResponse(_) => StatusCode::BAD_GATEWAY,
+            EmbeddingError::Tokenization(_) | EmbeddingError::Tensor(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut builder = HttpResponse::build(self.status_code());
+        if let EmbeddingError::RateLimited {
+            retry_after: Some(d),
+            ..
+        } = self
+        {
+            builder.insert_header(("Retry-After", d.as_secs().to_string()));
+        }
+        builder.json(ErrorBody {
+            error: ErrorBodyInner {
+                code: self.code(),
+                message: self.to_string(),
+            },
+        })
+    }
+}
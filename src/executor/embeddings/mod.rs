@@ -0,0 +1,422 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use futures::stream::{self, StreamExt};
+use tiktoken_rs::cl100k_base;
+
+use crate::handler::{CallbackHandlerFn, LlmModel};
+use crate::types::credentials::Credentials;
+use crate::types::gateway::{CreateEmbeddingRequest, EmbeddingUsage};
+
+mod encoding;
+mod error;
+mod remote;
+pub use encoding::{apply_dimensions, encode};
+pub use error::{EmbeddingError, FaultSource};
+
+/// The provider-agnostic embedding produced for a single input, tagged with
+/// the index of that input in the *original* request so callers can
+/// reassemble results regardless of the order sub-batches complete in.
+#[derive(Debug, Clone)]
+pub struct EmbeddingResult {
+    pub object: String,
+    pub embedding: Vec<f32>,
+    pub index: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct EmbeddingInvokeResult {
+    pub data: Vec<EmbeddingResult>,
+    pub usage: EmbeddingUsage,
+    /// The model that actually served the request, which may differ from
+    /// the one originally requested if a fallback was used.
+    pub served_by: String,
+}
+
+/// Default cooldown applied to a rate-limited provider when it doesn't tell
+/// us how long to back off for.
+const DEFAULT_RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(30);
+
+#[derive(Clone)]
+struct PendingInput {
+    index: usize,
+    text: String,
+    tokens: usize,
+}
+
+/// Greedily packs inputs into sub-batches whose cumulative token count stays
+/// at or under `max_tokens_per_batch`, preserving the original request order
+/// within and across batches. An input that alone exceeds the limit is put
+/// in a batch by itself.
+fn pack_into_batches(inputs: Vec<PendingInput>, max_tokens_per_batch: usize) -> Vec<Vec<PendingInput>> {
+    let mut batches: Vec<Vec<PendingInput>> = Vec::new();
+    let mut current: Vec<PendingInput> = Vec::new();
+    let mut current_tokens = 0usize;
+
+    for input in inputs {
+        if !current.is_empty() && current_tokens + input.tokens > max_tokens_per_batch {
+            batches.push(std::mem::take(&mut current));
+            current_tokens = 0;
+        }
+        current_tokens += input.tokens;
+        current.push(input);
+    }
+    if !current.is_empty() {
+        batches.push(current);
+    }
+    batches
+}
+
+/// Computes embeddings for every input in `request`, transparently batching
+/// and dispatching sub-batches to the provider with bounded concurrency so
+/// that large arrays (or inputs individually near the token ceiling) don't
+/// simply fail against the upstream per-request limit.
+///
+/// `llm_model` is tried first; if it fails with a provider-side fault (as
+/// opposed to a user or runtime fault), each model in its `fallback_chain`
+/// is tried in order. Models currently serving a rate-limit cooldown are
+/// skipped.
+pub async fn handle_embeddings_invoke(
+    request: CreateEmbeddingRequest,
+    callback_handler: &CallbackHandlerFn,
+    llm_model: &LlmModel,
+    credentials: Option<&Credentials>,
+) -> Result<EmbeddingInvokeResult, EmbeddingError> {
+    handle_embeddings_invoke_with_client(
+        request,
+        callback_handler,
+        llm_model,
+        credentials,
+        &DefaultProviderClient,
+    )
+    .await
+}
+
+/// The actual fallback/cooldown state machine, parameterized over how a
+/// sub-batch gets dispatched so tests can swap in a [`ProviderClient`] that
+/// never leaves the process instead of `DefaultProviderClient`'s real
+/// local-or-remote dispatch.
+async fn handle_embeddings_invoke_with_client(
+    request: CreateEmbeddingRequest,
+    callback_handler: &CallbackHandlerFn,
+    llm_model: &LlmModel,
+    credentials: Option<&Credentials>,
+    client: &dyn ProviderClient,
+) -> Result<EmbeddingInvokeResult, EmbeddingError> {
+    let bpe = cl100k_base()
+        .map_err(|e| EmbeddingError::Tokenization(format!("failed to load tokenizer: {e}")))?;
+
+    let max_tokens_per_batch = llm_model.max_tokens_per_batch();
+
+    let mut pending = Vec::new();
+    for (index, text) in request.input.into_vec().into_iter().enumerate() {
+        let tokens = bpe.encode_with_special_tokens(&text).len();
+        if tokens > max_tokens_per_batch {
+            return Err(EmbeddingError::InvalidInput(format!(
+                "input at index {index} has {tokens} tokens, which exceeds the {max_tokens_per_batch} token limit for model '{}'",
+                llm_model.model
+            )));
+        }
+        pending.push(PendingInput { index, text, tokens });
+    }
+
+    let batches = pack_into_batches(pending, max_tokens_per_batch);
+
+    let mut candidates = Vec::with_capacity(1 + llm_model.fallback_chain.len());
+    candidates.push(llm_model);
+    candidates.extend(llm_model.fallback_chain.iter());
+
+    let mut last_err = None;
+    for candidate in candidates {
+        if candidate.is_cooling_down() {
+            continue;
+        }
+
+        let concurrency = candidate.chunk_count_hint().max(1);
+        match dispatch_all_batches(batches.clone(), candidate, credentials, concurrency, client).await {
+            Ok((data, usage)) => {
+                callback_handler.notify("embeddings.invoke");
+                return Ok(EmbeddingInvokeResult {
+                    data,
+                    usage,
+                    served_by: candidate.model.clone(),
+                });
+            }
+            Err(mut errors) => {
+                // A user or runtime fault on any sub-batch fails the whole
+                // request immediately; trying another candidate won't help.
+                if let Some(pos) = errors.iter().position(|e| e.fault_source() != FaultSource::Provider) {
+                    return Err(errors.swap_remove(pos));
+                }
+                // `buffer_unordered` can land several sub-batch failures at
+                // once; inspect all of them rather than just the first one
+                // so a rate limit isn't missed because some other provider
+                // error happened to be collected first.
+                for err in &errors {
+                    if let EmbeddingError::RateLimited { retry_after, .. } = err {
+                        candidate.start_cooldown(retry_after.unwrap_or(DEFAULT_RATE_LIMIT_COOLDOWN));
+                    }
+                }
+                last_err = errors.into_iter().next();
+            }
+        }
+    }
+
+    Err(last_err.unwrap_or_else(|| {
+        EmbeddingError::ProviderUnavailable(
+            "no embedding provider available: every candidate is cooling down".into(),
+        )
+    }))
+}
+
+/// Dispatches every sub-batch against a single candidate model with bounded
+/// concurrency and folds the results back into original request order.
+async fn dispatch_all_batches(
+    batches: Vec<Vec<PendingInput>>,
+    llm_model: &LlmModel,
+    credentials: Option<&Credentials>,
+    concurrency: usize,
+    client: &dyn ProviderClient,
+) -> Result<(Vec<EmbeddingResult>, EmbeddingUsage), Vec<EmbeddingError>> {
+    let results = stream::iter(batches)
+        .map(|batch| dispatch_batch(batch, llm_model, credentials, client))
+        .buffer_unordered(concurrency)
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut data = Vec::new();
+    let mut usage = EmbeddingUsage::default();
+    let mut errors = Vec::new();
+    for batch_result in results {
+        match batch_result {
+            Ok((batch_data, batch_usage)) => {
+                usage.prompt_tokens += batch_usage.prompt_tokens;
+                usage.total_tokens += batch_usage.total_tokens;
+                data.extend(batch_data);
+            }
+            Err(err) => errors.push(err),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    data.sort_by_key(|d| d.index);
+
+    Ok((data, usage))
+}
+
+/// Sends one sub-batch to the upstream provider and maps the response back
+/// onto the original request indices carried by `batch`.
+async fn dispatch_batch(
+    batch: Vec<PendingInput>,
+    llm_model: &LlmModel,
+    credentials: Option<&Credentials>,
+    client: &dyn ProviderClient,
+) -> Result<(Vec<EmbeddingResult>, EmbeddingUsage), EmbeddingError> {
+    let indices: Vec<usize> = batch.iter().map(|i| i.index).collect();
+    let texts: Vec<String> = batch.into_iter().map(|i| i.text).collect();
+
+    let response = client.embed(llm_model, credentials, &texts).await?;
+
+    if response.embeddings.len() != indices.len() {
+        return Err(EmbeddingError::ProviderResponse(format!(
+            "provider '{}' returned {} embedding(s) for {} input(s)",
+            llm_model.provider,
+            response.embeddings.len(),
+            indices.len()
+        )));
+    }
+
+    let data = response
+        .embeddings
+        .into_iter()
+        .zip(indices)
+        .map(|(embedding, index)| EmbeddingResult {
+            object: "embedding".into(),
+            embedding,
+            index,
+        })
+        .collect();
+
+    Ok((data, response.usage))
+}
+
+struct ProviderEmbeddingResponse {
+    embeddings: Vec<Vec<f32>>,
+    usage: EmbeddingUsage,
+}
+
+/// Dispatches one sub-batch to whichever provider `llm_model` resolves to.
+/// Models configured with a local embedding handle never leave the process;
+/// everything else goes out over the network to an OpenAI-compatible
+/// `/embeddings` endpoint.
+async fn call_provider(
+    llm_model: &LlmModel,
+    credentials: Option<&Credentials>,
+    texts: &[String],
+) -> Result<ProviderEmbeddingResponse, EmbeddingError> {
+    if let Some(local) = &llm_model.local_embedding {
+        let output = local.embed(texts.to_vec()).await?;
+        return Ok(ProviderEmbeddingResponse {
+            embeddings: output.embeddings,
+            usage: EmbeddingUsage {
+                prompt_tokens: output.prompt_tokens,
+                total_tokens: output.prompt_tokens,
+            },
+        });
+    }
+
+    remote::call_remote(llm_model, credentials, texts).await
+}
+
+type ProviderFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<ProviderEmbeddingResponse, EmbeddingError>> + Send + 'a>>;
+
+/// Indirection around `call_provider` so the fallback/cooldown state machine
+/// in `handle_embeddings_invoke_with_client` can be exercised against a
+/// fake that never makes a real network call or loads a real model.
+trait ProviderClient: Send + Sync {
+    fn embed<'a>(
+        &'a self,
+        llm_model: &'a LlmModel,
+        credentials: Option<&'a Credentials>,
+        texts: &'a [String],
+    ) -> ProviderFuture<'a>;
+}
+
+struct DefaultProviderClient;
+
+impl ProviderClient for DefaultProviderClient {
+    fn embed<'a>(
+        &'a self,
+        llm_model: &'a LlmModel,
+        credentials: Option<&'a Credentials>,
+        texts: &'a [String],
+    ) -> ProviderFuture<'a> {
+        Box::pin(call_provider(llm_model, credentials, texts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(index: usize, tokens: usize) -> PendingInput {
+        PendingInput {
+            index,
+            text: format!("input-{index}"),
+            tokens,
+        }
+    }
+
+    #[test]
+    fn packs_up_to_exact_limit_into_one_batch() {
+        let inputs = vec![input(0, 5), input(1, 5)];
+        let batches = pack_into_batches(inputs, 10);
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].len(), 2);
+    }
+
+    #[test]
+    fn starts_a_new_batch_once_the_limit_would_be_exceeded() {
+        let inputs = vec![input(0, 5), input(1, 5), input(2, 1)];
+        let batches = pack_into_batches(inputs, 10);
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].len(), 2);
+        assert_eq!(batches[1].len(), 1);
+    }
+
+    #[test]
+    fn a_single_oversized_input_gets_its_own_batch() {
+        let inputs = vec![input(0, 20)];
+        let batches = pack_into_batches(inputs, 10);
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].len(), 1);
+    }
+
+    /// Returns `Err(RateLimited)` for the model named "primary" and a
+    /// one-embedding-per-input success for every other model, so tests can
+    /// drive the fallback chain without a real provider.
+    struct FakeProviderClient;
+
+    impl ProviderClient for FakeProviderClient {
+        fn embed<'a>(
+            &'a self,
+            llm_model: &'a LlmModel,
+            _credentials: Option<&'a Credentials>,
+            texts: &'a [String],
+        ) -> ProviderFuture<'a> {
+            let is_primary = llm_model.model == "primary";
+            let count = texts.len();
+            Box::pin(async move {
+                if is_primary {
+                    return Err(EmbeddingError::RateLimited {
+                        message: "primary is rate limiting requests".into(),
+                        retry_after: Some(Duration::from_secs(1)),
+                    });
+                }
+                Ok(ProviderEmbeddingResponse {
+                    embeddings: (0..count).map(|_| vec![0.0_f32; 2]).collect(),
+                    usage: EmbeddingUsage {
+                        prompt_tokens: count as u32,
+                        total_tokens: count as u32,
+                    },
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_the_next_candidate_when_the_primary_is_rate_limited() {
+        let secondary = LlmModel::new("secondary", "openai");
+        let primary = LlmModel::new("primary", "openai").with_fallback(secondary);
+
+        let request = CreateEmbeddingRequest {
+            model: primary.model.clone(),
+            input: crate::types::gateway::EmbeddingInput::String("hello".into()),
+            user: None,
+            encoding_format: None,
+            dimensions: None,
+        };
+
+        let result = handle_embeddings_invoke_with_client(
+            request,
+            &CallbackHandlerFn::default(),
+            &primary,
+            None,
+            &FakeProviderClient,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.served_by, "secondary");
+        assert!(primary.is_cooling_down());
+    }
+
+    #[tokio::test]
+    async fn rate_limit_with_no_fallback_chain_propagates_and_starts_a_cooldown() {
+        let primary = LlmModel::new("primary", "openai");
+
+        let request = CreateEmbeddingRequest {
+            model: primary.model.clone(),
+            input: crate::types::gateway::EmbeddingInput::String("hello".into()),
+            user: None,
+            encoding_format: None,
+            dimensions: None,
+        };
+
+        let err = handle_embeddings_invoke_with_client(
+            request,
+            &CallbackHandlerFn::default(),
+            &primary,
+            None,
+            &FakeProviderClient,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(err, EmbeddingError::RateLimited { .. }));
+        assert!(primary.is_cooling_down());
+    }
+}
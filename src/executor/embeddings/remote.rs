@@ -0,0 +1,176 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::handler::LlmModel;
+use crate::types::credentials::Credentials;
+use crate::types::gateway::EmbeddingUsage;
+
+use super::{EmbeddingError, ProviderEmbeddingResponse};
+
+/// Default base URL used when a model doesn't configure an explicit
+/// `endpoint`, keyed by provider name.
+fn default_endpoint(provider: &str) -> Result<&'static str, EmbeddingError> {
+    match provider {
+        "openai" => Ok("https://api.openai.com/v1"),
+        other => Err(EmbeddingError::ProviderUnavailable(format!(
+            "provider '{other}' has no default endpoint configured; set LlmModel::endpoint"
+        ))),
+    }
+}
+
+#[derive(Deserialize)]
+struct OpenAiEmbeddingItem {
+    embedding: Vec<f32>,
+    index: usize,
+}
+
+#[derive(Deserialize)]
+struct OpenAiEmbeddingUsage {
+    prompt_tokens: u32,
+    total_tokens: u32,
+}
+
+#[derive(Deserialize)]
+struct OpenAiEmbeddingResponse {
+    data: Vec<OpenAiEmbeddingItem>,
+    usage: OpenAiEmbeddingUsage,
+}
+
+/// Calls an OpenAI-compatible `POST /embeddings` endpoint for providers that
+/// don't have an in-process handle (i.e. everything other than `"local"`).
+pub async fn call_remote(
+    llm_model: &LlmModel,
+    credentials: Option<&Credentials>,
+    texts: &[String],
+) -> Result<ProviderEmbeddingResponse, EmbeddingError> {
+    let base = match &llm_model.endpoint {
+        Some(endpoint) => endpoint.trim_end_matches('/'),
+        None => default_endpoint(&llm_model.provider)?,
+    };
+    let url = format!("{base}/embeddings");
+
+    let mut request = reqwest::Client::new().post(&url).json(&serde_json::json!({
+        "model": llm_model.model,
+        "input": texts,
+    }));
+    if let Some(credentials) = credentials {
+        request = request.bearer_auth(&credentials.api_key);
+    }
+
+    let response = request.send().await.map_err(|e| {
+        EmbeddingError::ProviderUnavailable(format!("request to '{}' failed: {e}", llm_model.provider))
+    })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(Duration::from_secs);
+        let body = response.text().await.unwrap_or_default();
+        return Err(map_error_response(&llm_model.provider, status, body, retry_after));
+    }
+
+    let parsed: OpenAiEmbeddingResponse = response.json().await.map_err(|e| {
+        EmbeddingError::ProviderResponse(format!(
+            "unexpected response body from provider '{}': {e}",
+            llm_model.provider
+        ))
+    })?;
+
+    let embeddings = embeddings_in_index_order(parsed.data);
+
+    Ok(ProviderEmbeddingResponse {
+        embeddings,
+        usage: EmbeddingUsage {
+            prompt_tokens: parsed.usage.prompt_tokens,
+            total_tokens: parsed.usage.total_tokens,
+        },
+    })
+}
+
+/// Maps a non-2xx response from an OpenAI-compatible `/embeddings` endpoint
+/// onto the right [`EmbeddingError`] variant. A rate limit is recognised from
+/// the HTTP status alone; its message does not include the response body.
+fn map_error_response(
+    provider: &str,
+    status: reqwest::StatusCode,
+    body: String,
+    retry_after: Option<Duration>,
+) -> EmbeddingError {
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        EmbeddingError::ProviderAuth(format!("provider '{provider}' rejected credentials: {body}"))
+    } else if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        EmbeddingError::RateLimited {
+            message: format!("provider '{provider}' is rate limiting requests"),
+            retry_after,
+        }
+    } else {
+        EmbeddingError::ProviderResponse(format!("provider '{provider}' returned {status}: {body}"))
+    }
+}
+
+/// The embedding for input `i` isn't guaranteed to land at index `i` in the
+/// response (providers are free to reorder), so sort by the index the
+/// provider tagged each item with before handing embeddings back in
+/// sub-batch order.
+fn embeddings_in_index_order(mut items: Vec<OpenAiEmbeddingItem>) -> Vec<Vec<f32>> {
+    items.sort_by_key(|item| item.index);
+    items.into_iter().map(|item| item.embedding).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unauthorized_maps_to_provider_auth() {
+        let err = map_error_response("openai", reqwest::StatusCode::UNAUTHORIZED, "nope".into(), None);
+        assert!(matches!(err, EmbeddingError::ProviderAuth(_)));
+    }
+
+    #[test]
+    fn forbidden_maps_to_provider_auth() {
+        let err = map_error_response("openai", reqwest::StatusCode::FORBIDDEN, "nope".into(), None);
+        assert!(matches!(err, EmbeddingError::ProviderAuth(_)));
+    }
+
+    #[test]
+    fn too_many_requests_maps_to_rate_limited_with_retry_after() {
+        let err = map_error_response(
+            "openai",
+            reqwest::StatusCode::TOO_MANY_REQUESTS,
+            "slow down".into(),
+            Some(Duration::from_secs(5)),
+        );
+        match err {
+            EmbeddingError::RateLimited { retry_after, .. } => {
+                assert_eq!(retry_after, Some(Duration::from_secs(5)));
+            }
+            other => panic!("expected RateLimited, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn server_error_maps_to_provider_response() {
+        let err = map_error_response(
+            "openai",
+            reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+            "boom".into(),
+            None,
+        );
+        assert!(matches!(err, EmbeddingError::ProviderResponse(_)));
+    }
+
+    #[test]
+    fn embeddings_are_reordered_to_match_their_original_index() {
+        let items = vec![
+            OpenAiEmbeddingItem { embedding: vec![1.0], index: 1 },
+            OpenAiEmbeddingItem { embedding: vec![0.0], index: 0 },
+        ];
+        assert_eq!(embeddings_in_index_order(items), vec![vec![0.0], vec![1.0]]);
+    }
+}
@@ -0,0 +1,215 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use candle_core::{DType, Device, Tensor};
+use candle_transformers::models::bert::{BertModel, Config as BertConfig};
+use tokenizers::{PaddingParams, PaddingStrategy, Tokenizer};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::executor::embeddings::EmbeddingError;
+
+/// Result of running the local model over a batch of inputs.
+pub struct LocalEmbeddingOutput {
+    pub embeddings: Vec<Vec<f32>>,
+    pub prompt_tokens: u32,
+}
+
+struct EmbedJob {
+    texts: Vec<String>,
+    reply: oneshot::Sender<Result<LocalEmbeddingOutput, EmbeddingError>>,
+}
+
+/// An in-process embedding model (e.g. BGE or another sentence-transformers
+/// BERT checkpoint) served without any upstream API call.
+///
+/// The config/tokenizer/weights are loaded once at startup. `candle`'s
+/// tensors are not `Sync`, so the loaded handle cannot be captured directly
+/// by actix's per-worker request closures; instead it lives on a single
+/// dedicated blocking task and all requests are funneled to it over an mpsc
+/// channel, with the response routed back through a oneshot per job.
+pub struct LocalEmbeddingProvider {
+    job_tx: mpsc::Sender<EmbedJob>,
+}
+
+impl std::fmt::Debug for LocalEmbeddingProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalEmbeddingProvider").finish()
+    }
+}
+
+impl LocalEmbeddingProvider {
+    /// Loads the model from `model_dir` (expected to contain `config.json`,
+    /// `tokenizer.json` and `model.safetensors`) and starts the dedicated
+    /// worker task that owns it for the lifetime of the process.
+    ///
+    /// Loading happens on the worker task (candle's tensors aren't `Send`
+    /// across an `await`, so the blocking load has to happen wherever the
+    /// weights end up living), but this function doesn't return until that
+    /// load has actually finished, so a bad `model_dir` fails startup
+    /// instead of surfacing as a mysterious error on the first request.
+    pub async fn load(model_dir: impl AsRef<Path>) -> Result<Arc<Self>, EmbeddingError> {
+        let model_dir = model_dir.as_ref().to_path_buf();
+        let (job_tx, mut job_rx) = mpsc::channel::<EmbedJob>(32);
+        let (ready_tx, ready_rx) = oneshot::channel::<Result<(), String>>();
+
+        tokio::task::spawn_blocking(move || {
+            let model = match BgeModel::load(&model_dir) {
+                Ok(model) => model,
+                Err(err) => {
+                    let _ = ready_tx.send(Err(format!(
+                        "failed to load local embedding model from {model_dir:?}: {err}"
+                    )));
+                    return;
+                }
+            };
+            if ready_tx.send(Ok(())).is_err() {
+                // Nobody is waiting on us any more (the `load` future was
+                // dropped before the model finished loading), so there's no
+                // point starting the worker loop.
+                return;
+            }
+            while let Some(job) = job_rx.blocking_recv() {
+                let result = model.embed_batch(&job.texts);
+                let _ = job.reply.send(result);
+            }
+        });
+
+        match ready_rx.await {
+            Ok(Ok(())) => Ok(Arc::new(Self { job_tx })),
+            Ok(Err(message)) => Err(EmbeddingError::Tensor(message)),
+            Err(_) => Err(EmbeddingError::Tensor(
+                "local embedding worker exited before finishing startup".into(),
+            )),
+        }
+    }
+
+    pub async fn embed(&self, texts: Vec<String>) -> Result<LocalEmbeddingOutput, EmbeddingError> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.job_tx
+            .send(EmbedJob {
+                texts,
+                reply: reply_tx,
+            })
+            .await
+            .map_err(|_| {
+                EmbeddingError::Tensor("local embedding worker is not running".into())
+            })?;
+        reply_rx.await.map_err(|_| {
+            EmbeddingError::Tensor("local embedding worker dropped the response channel".into())
+        })?
+    }
+}
+
+/// The loaded BERT/BGE weights plus tokenizer, owned exclusively by the
+/// worker task spawned in `LocalEmbeddingProvider::load`.
+struct BgeModel {
+    model: BertModel,
+    tokenizer: Tokenizer,
+    device: Device,
+    max_position_embeddings: usize,
+}
+
+impl BgeModel {
+    fn load(model_dir: &PathBuf) -> anyhow::Result<Self> {
+        let device = Device::Cpu;
+        let config: BertConfig =
+            serde_json::from_slice(&std::fs::read(model_dir.join("config.json"))?)?;
+        let mut tokenizer = Tokenizer::from_file(model_dir.join("tokenizer.json"))
+            .map_err(|e| anyhow::anyhow!("failed to load tokenizer: {e}"))?;
+        // The input tensor built in `embed_batch` needs every row of the
+        // batch to have the same length, but a sub-batch can mix inputs of
+        // very different lengths,
+        // so pad to the longest sequence in the batch. Truncation is
+        // deliberately left unconfigured: the gateway's pre-dispatch token
+        // check only approximates this tokenizer's own counts (see
+        // `handle_embeddings_invoke`), and silently truncating whatever
+        // slips past that check would return an embedding for less text
+        // than the caller submitted. `embed_batch` below rejects anything
+        // over `max_position_embeddings` instead.
+        tokenizer.with_padding(Some(PaddingParams {
+            strategy: PaddingStrategy::BatchLongest,
+            ..Default::default()
+        }));
+        let weights = candle_core::safetensors::load(model_dir.join("model.safetensors"), &device)?;
+        let vb = candle_nn::VarBuilder::from_tensors(weights, DType::F32, &device);
+        let model = BertModel::load(vb, &config)?;
+        Ok(Self {
+            model,
+            tokenizer,
+            device,
+            max_position_embeddings: config.max_position_embeddings,
+        })
+    }
+
+    /// Tokenizes `texts`, runs the forward pass, mean-pools the last hidden
+    /// state over the token dimension using the attention mask, and
+    /// L2-normalizes each resulting vector to unit length.
+    fn embed_batch(&self, texts: &[String]) -> Result<LocalEmbeddingOutput, EmbeddingError> {
+        let encodings = self
+            .tokenizer
+            .encode_batch(texts.to_vec(), true)
+            .map_err(|e| EmbeddingError::Tokenization(format!("tokenization failed: {e}")))?;
+
+        // With truncation disabled, the attention mask's true-token count
+        // (padding is always 0) is exactly how many tokens this tokenizer
+        // produced for that input, untouched by the batch's padding.
+        if let Some(over) = encodings.iter().find_map(|e| {
+            let real_tokens = e.get_attention_mask().iter().filter(|&&m| m == 1).count();
+            (real_tokens > self.max_position_embeddings).then_some(real_tokens)
+        }) {
+            return Err(EmbeddingError::InvalidInput(format!(
+                "input tokenizes to {over} tokens for the local embedding model, which exceeds its {} token limit",
+                self.max_position_embeddings
+            )));
+        }
+
+        let prompt_tokens: u32 = encodings.iter().map(|e| e.get_ids().len() as u32).sum();
+
+        let token_ids: Vec<Vec<u32>> = encodings.iter().map(|e| e.get_ids().to_vec()).collect();
+        let attention_mask: Vec<Vec<u32>> =
+            encodings.iter().map(|e| e.get_attention_mask().to_vec()).collect();
+
+        let token_ids = Tensor::new(token_ids, &self.device)
+            .map_err(|e| EmbeddingError::Tensor(format!("failed to build input tensor: {e}")))?;
+        let mask = Tensor::new(attention_mask, &self.device)
+            .map_err(|e| EmbeddingError::Tensor(format!("failed to build mask tensor: {e}")))?;
+        let token_type_ids = token_ids
+            .zeros_like()
+            .map_err(|e| EmbeddingError::Tensor(format!("failed to build segment tensor: {e}")))?;
+
+        let hidden_state = self
+            .model
+            .forward(&token_ids, &token_type_ids, Some(&mask))
+            .map_err(|e| EmbeddingError::Tensor(format!("forward pass failed: {e}")))?;
+
+        let embeddings = mean_pool(&hidden_state, &mask)
+            .map_err(|e| EmbeddingError::Tensor(format!("pooling failed: {e}")))?;
+        let embeddings = l2_normalize(&embeddings)
+            .map_err(|e| EmbeddingError::Tensor(format!("normalization failed: {e}")))?;
+
+        let embeddings = embeddings
+            .to_vec2::<f32>()
+            .map_err(|e| EmbeddingError::Tensor(format!("failed to read output tensor: {e}")))?;
+
+        Ok(LocalEmbeddingOutput {
+            embeddings,
+            prompt_tokens,
+        })
+    }
+}
+
+/// Mean-pools `hidden_state` (`[batch, seq, hidden]`) over the token
+/// dimension, weighting each token by `mask` (`[batch, seq]`) so padding
+/// doesn't dilute the pooled vector.
+fn mean_pool(hidden_state: &Tensor, mask: &Tensor) -> candle_core::Result<Tensor> {
+    let mask = mask.to_dtype(hidden_state.dtype())?.unsqueeze(2)?;
+    let masked = hidden_state.broadcast_mul(&mask)?;
+    let summed = masked.sum(1)?;
+    let counts = mask.sum(1)?.clamp(1e-9, f64::INFINITY)?;
+    summed.broadcast_div(&counts)
+}
+
+fn l2_normalize(embeddings: &Tensor) -> candle_core::Result<Tensor> {
+    let norm = embeddings.sqr()?.sum_keepdim(1)?.sqrt()?;
+    embeddings.broadcast_div(&norm)
+}
@@ -0,0 +1,3 @@
+pub mod embeddings;
+pub mod local_embedding;
+pub mod vector_store;
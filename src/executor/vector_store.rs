@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::embeddings::EmbeddingError;
+
+/// Distance metric a collection was indexed with. Mirrors the metrics a
+/// Qdrant-style vector store exposes per-collection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DistanceMetric {
+    Cosine,
+    Dot,
+    Euclidean,
+}
+
+/// A single retrievable collection: which embedding model it was indexed
+/// with, its vector dimension, and the defaults to search it with.
+#[derive(Debug, Clone)]
+pub struct CollectionConfig {
+    pub name: String,
+    pub dimension: usize,
+    pub distance: DistanceMetric,
+    pub embedding_model: String,
+    pub default_k: usize,
+}
+
+/// Connection details for the vector store backing `/retrieve`, plus the
+/// collections it's configured to search.
+#[derive(Debug, Clone, Default)]
+pub struct VectorStoreConfig {
+    pub endpoint: String,
+    pub collections: Vec<CollectionConfig>,
+}
+
+impl VectorStoreConfig {
+    pub fn find_collection(&self, name: &str) -> Option<&CollectionConfig> {
+        self.collections.iter().find(|c| c.name == name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RetrievedDocument {
+    pub id: String,
+    pub score: f32,
+    pub content: String,
+    pub metadata: Value,
+}
+
+#[derive(Deserialize)]
+struct QdrantSearchResponse {
+    result: Vec<QdrantPoint>,
+}
+
+#[derive(Deserialize)]
+struct QdrantPoint {
+    id: Value,
+    score: f32,
+    #[serde(default)]
+    payload: Value,
+}
+
+/// Searches `collection` for the `k` nearest neighbours of `query_embedding`
+/// against a Qdrant-style `POST /collections/{name}/points/search` API.
+pub async fn search(
+    store: &VectorStoreConfig,
+    collection: &CollectionConfig,
+    query_embedding: &[f32],
+    k: usize,
+) -> Result<Vec<RetrievedDocument>, EmbeddingError> {
+    let url = format!(
+        "{}/collections/{}/points/search",
+        store.endpoint.trim_end_matches('/'),
+        collection.name
+    );
+
+    let response = reqwest::Client::new()
+        .post(&url)
+        .json(&serde_json::json!({
+            "vector": query_embedding,
+            "limit": k,
+            "with_payload": true,
+        }))
+        .send()
+        .await
+        .map_err(|e| EmbeddingError::ProviderUnavailable(format!("vector store request failed: {e}")))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(EmbeddingError::ProviderResponse(format!(
+            "vector store returned {status}: {body}"
+        )));
+    }
+
+    let parsed: QdrantSearchResponse = response
+        .json()
+        .await
+        .map_err(|e| EmbeddingError::ProviderResponse(format!("unexpected vector store response: {e}")))?;
+
+    Ok(parsed
+        .result
+        .into_iter()
+        .map(|point| {
+            let content = point
+                .payload
+                .get("content")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+            RetrievedDocument {
+                id: point_id_to_string(&point.id),
+                score: point.score,
+                content,
+                metadata: point.payload,
+            }
+        })
+        .collect())
+}
+
+/// Qdrant point ids are either a string (commonly a UUID) or an unsigned
+/// integer. `Value::to_string()` would serialize a string id back as
+/// JSON, quotes included, so the two cases are matched explicitly instead.
+fn point_id_to_string(id: &Value) -> String {
+    match id {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_ids_are_returned_unquoted() {
+        let id = Value::String("a3f1c2e4-uuid".into());
+        assert_eq!(point_id_to_string(&id), "a3f1c2e4-uuid");
+    }
+
+    #[test]
+    fn numeric_ids_are_formatted_without_decoration() {
+        let id = Value::Number(42.into());
+        assert_eq!(point_id_to_string(&id), "42");
+    }
+}
@@ -1,4 +1,4 @@
-use crate::executor::embeddings::handle_embeddings_invoke;
+use crate::executor::embeddings::{apply_dimensions, encode, handle_embeddings_invoke, EmbeddingError};
 use crate::types::credentials::Credentials;
 use actix_web::{web, HttpResponse};
 use actix_web::{HttpMessage, HttpRequest};
@@ -9,7 +9,6 @@ use crate::types::gateway::{
 
 use crate::handler::AvailableModels;
 use crate::handler::CallbackHandlerFn;
-use crate::GatewayApiError;
 
 use super::find_model_by_full_name;
 
@@ -18,12 +17,15 @@ pub async fn embeddings_handler(
     models: web::Data<AvailableModels>,
     callback_handler: web::Data<CallbackHandlerFn>,
     req: HttpRequest,
-) -> Result<HttpResponse, GatewayApiError> {
+) -> Result<HttpResponse, EmbeddingError> {
     let request = request.into_inner();
     let available_models = models.into_inner();
     let llm_model = find_model_by_full_name(&request.model, &available_models)?;
     let key_credentials = req.extensions().get::<Credentials>().cloned();
 
+    let encoding_format = request.encoding_format.unwrap_or_default();
+    let dimensions = request.dimensions;
+
     let result = handle_embeddings_invoke(
         request,
         callback_handler.get_ref(),
@@ -32,20 +34,20 @@ pub async fn embeddings_handler(
     )
     .await?;
 
-    let data = result
-        .data
-        .iter()
-        .map(|v| EmbeddingData {
-            object: v.object.clone(),
-            embedding: v.embedding.clone(),
+    let mut data = Vec::with_capacity(result.data.len());
+    for v in result.data {
+        let embedding = apply_dimensions(v.embedding, dimensions)?;
+        data.push(EmbeddingData {
+            object: v.object,
+            embedding: encode(embedding, encoding_format),
             index: v.index,
-        })
-        .collect();
+        });
+    }
 
     Ok(HttpResponse::Ok().json(CreateEmbeddingResponse {
         object: "list".into(),
         data,
-        model: llm_model.model.clone(),
+        model: result.served_by.clone(),
         usage: EmbeddingUsage {
             prompt_tokens: result.usage.prompt_tokens,
             total_tokens: result.usage.total_tokens,
@@ -0,0 +1,138 @@
+pub mod embedding;
+pub mod retrieve;
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::executor::local_embedding::LocalEmbeddingProvider;
+use crate::GatewayApiError;
+
+/// A model the gateway is configured to route to, identified by the
+/// `provider/model` name clients pass in the `model` field of a request.
+#[derive(Debug, Clone)]
+pub struct LlmModel {
+    pub model: String,
+    pub provider: String,
+    pub endpoint: Option<String>,
+    /// Set when `provider` is `"local"`: the long-lived handle to the
+    /// in-process embedding model, loaded once at startup.
+    pub local_embedding: Option<Arc<LocalEmbeddingProvider>>,
+    /// Other models to fall back to, in order, if this one fails with a
+    /// provider-side fault. Empty for a model with no configured fallback.
+    pub fallback_chain: Vec<LlmModel>,
+    /// Shared across every clone of this model (the registry only ever
+    /// loads one `LlmModel` per configured provider and clones it out), so a
+    /// rate-limit cooldown recorded for one request is honored by the next.
+    cooldown_until: Arc<Mutex<Option<Instant>>>,
+}
+
+impl LlmModel {
+    /// Builds a model with no endpoint override, no fallback chain, and no
+    /// active cooldown. Callers that need those set them with the `with_*`
+    /// builders below.
+    pub fn new(model: impl Into<String>, provider: impl Into<String>) -> Self {
+        Self {
+            model: model.into(),
+            provider: provider.into(),
+            endpoint: None,
+            local_embedding: None,
+            fallback_chain: Vec::new(),
+            cooldown_until: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    pub fn with_local_embedding(mut self, provider: Arc<LocalEmbeddingProvider>) -> Self {
+        self.local_embedding = Some(provider);
+        self
+    }
+
+    /// Appends `fallback` to the end of this model's fallback chain.
+    pub fn with_fallback(mut self, fallback: LlmModel) -> Self {
+        self.fallback_chain.push(fallback);
+        self
+    }
+
+    /// How many sub-batches this provider can reasonably be asked to serve
+    /// concurrently for a single logical request. Providers with stricter
+    /// rate limits should return a lower number here.
+    pub fn chunk_count_hint(&self) -> usize {
+        match self.provider.as_str() {
+            "openai" => 4,
+            "azure_openai" => 2,
+            _ => 1,
+        }
+    }
+
+    /// Upper bound on the number of tokens packed into a single request to
+    /// this provider. OpenAI rejects embedding requests whose combined
+    /// input exceeds roughly 8191 tokens; the local provider's context
+    /// window is typically much smaller.
+    pub fn max_tokens_per_batch(&self) -> usize {
+        match self.provider.as_str() {
+            "local" => 512,
+            _ => 8191,
+        }
+    }
+
+    /// Whether this model is currently serving a rate-limit cooldown and
+    /// should be skipped in favor of the next candidate in a fallback chain.
+    pub fn is_cooling_down(&self) -> bool {
+        match *self.cooldown_until.lock().expect("cooldown mutex poisoned") {
+            Some(until) => Instant::now() < until,
+            None => false,
+        }
+    }
+
+    /// Records that this model just rate-limited us, so it's skipped until
+    /// `duration` elapses.
+    pub fn start_cooldown(&self, duration: Duration) {
+        *self.cooldown_until.lock().expect("cooldown mutex poisoned") = Some(Instant::now() + duration);
+    }
+}
+
+/// The set of models this gateway instance is configured to serve, loaded
+/// once at startup and shared across actix workers via `web::Data`.
+#[derive(Debug, Clone, Default)]
+pub struct AvailableModels(pub Vec<LlmModel>);
+
+/// Looks up a configured model by its fully qualified name (e.g.
+/// `openai/text-embedding-3-small`), returning a user-facing error if it
+/// isn't one the gateway knows about.
+pub fn find_model_by_full_name(
+    full_name: &str,
+    models: &Arc<AvailableModels>,
+) -> Result<LlmModel, GatewayApiError> {
+    models
+        .0
+        .iter()
+        .find(|m| m.model == full_name)
+        .cloned()
+        .ok_or_else(|| GatewayApiError::NotFound(format!("model '{full_name}' not found")))
+}
+
+/// Optional hook invoked with bookkeeping events (usage, latency, errors) as
+/// requests flow through the gateway. Wrapped in `Arc` so it can be cloned
+/// into `web::Data` and shared across workers.
+#[derive(Clone, Default)]
+pub struct CallbackHandlerFn(pub Option<Arc<dyn Fn(&str) + Send + Sync>>);
+
+impl CallbackHandlerFn {
+    pub fn notify(&self, event: &str) {
+        if let Some(f) = &self.0 {
+            f(event);
+        }
+    }
+}
+
+impl std::fmt::Debug for CallbackHandlerFn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CallbackHandlerFn")
+            .field("set", &self.0.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,74 @@
+use actix_web::{web, HttpResponse};
+use actix_web::{HttpMessage, HttpRequest};
+
+use crate::executor::embeddings::{handle_embeddings_invoke, EmbeddingError};
+use crate::executor::vector_store::{search, VectorStoreConfig};
+use crate::types::credentials::Credentials;
+use crate::types::gateway::{CreateEmbeddingRequest, EmbeddingInput, RetrieveRequest, RetrieveResponse};
+
+use crate::handler::AvailableModels;
+use crate::handler::CallbackHandlerFn;
+
+use super::find_model_by_full_name;
+
+/// Embeds `request.query` with the model the target collection was indexed
+/// with, then searches that collection for the `k` nearest documents.
+pub async fn retrieve_handler(
+    request: web::Json<RetrieveRequest>,
+    models: web::Data<AvailableModels>,
+    callback_handler: web::Data<CallbackHandlerFn>,
+    vector_store: web::Data<VectorStoreConfig>,
+    req: HttpRequest,
+) -> Result<HttpResponse, EmbeddingError> {
+    let request = request.into_inner();
+    let available_models = models.into_inner();
+
+    let collection = vector_store
+        .find_collection(&request.collection)
+        .ok_or_else(|| EmbeddingError::CollectionNotFound(request.collection.clone()))?;
+
+    let llm_model = find_model_by_full_name(&collection.embedding_model, &available_models)?;
+    let key_credentials = req.extensions().get::<Credentials>().cloned();
+
+    let embed_request = CreateEmbeddingRequest {
+        model: collection.embedding_model.clone(),
+        input: EmbeddingInput::String(request.query),
+        user: None,
+        encoding_format: None,
+        dimensions: None,
+    };
+
+    let result = handle_embeddings_invoke(
+        embed_request,
+        callback_handler.get_ref(),
+        &llm_model,
+        key_credentials.as_ref(),
+    )
+    .await?;
+
+    let query_embedding = result
+        .data
+        .into_iter()
+        .next()
+        .ok_or_else(|| EmbeddingError::ProviderResponse("provider returned no embedding for the query".into()))?
+        .embedding;
+
+    if query_embedding.len() != collection.dimension {
+        return Err(EmbeddingError::DimensionMismatch(format!(
+            "model '{}' produces {}-dimensional embeddings but collection '{}' is configured for {}",
+            collection.embedding_model,
+            query_embedding.len(),
+            collection.name,
+            collection.dimension
+        )));
+    }
+
+    let k = request.k.unwrap_or(collection.default_k);
+    let documents = search(&vector_store, collection, &query_embedding, k).await?;
+
+    Ok(HttpResponse::Ok().json(RetrieveResponse {
+        object: "list".into(),
+        model: result.served_by,
+        documents,
+    }))
+}
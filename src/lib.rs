@@ -0,0 +1,6 @@
+pub mod error;
+pub mod executor;
+pub mod handler;
+pub mod types;
+
+pub use error::GatewayApiError;
@@ -0,0 +1,7 @@
+/// Per-request credentials resolved from an inbound API key, attached to the
+/// request extensions by the auth middleware and picked up by handlers that
+/// need to forward them to an upstream provider.
+#[derive(Debug, Clone, Default)]
+pub struct Credentials {
+    pub api_key: String,
+}
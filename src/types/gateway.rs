@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+/// A single embedding input, or a batch of inputs submitted in one request.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum EmbeddingInput {
+    String(String),
+    StringArray(Vec<String>),
+}
+
+impl EmbeddingInput {
+    /// Flattens the input into an ordered list of strings, one per logical
+    /// embedding that the caller expects back.
+    pub fn into_vec(self) -> Vec<String> {
+        match self {
+            EmbeddingInput::String(s) => vec![s],
+            EmbeddingInput::StringArray(v) => v,
+        }
+    }
+}
+
+/// How the caller wants `EmbeddingData.embedding` represented on the wire.
+/// `base64` cuts JSON payload size for large batches by packing each vector
+/// as little-endian `f32` bytes instead of a JSON number array.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EncodingFormat {
+    #[default]
+    Float,
+    Base64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CreateEmbeddingRequest {
+    pub model: String,
+    pub input: EmbeddingInput,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding_format: Option<EncodingFormat>,
+    /// Requests a truncated (Matryoshka-style) output vector instead of the
+    /// model's native dimension. Must not exceed the native dimension.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<usize>,
+}
+
+/// The embedding vector in whichever representation the client requested.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum EmbeddingVector {
+    Float(Vec<f32>),
+    Base64(String),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddingData {
+    pub object: String,
+    pub embedding: EmbeddingVector,
+    pub index: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize)]
+pub struct EmbeddingUsage {
+    pub prompt_tokens: u32,
+    pub total_tokens: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateEmbeddingResponse {
+    pub object: String,
+    pub data: Vec<EmbeddingData>,
+    pub model: String,
+    pub usage: EmbeddingUsage,
+}
+
+/// A natural-language query against a named vector store collection.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RetrieveRequest {
+    pub query: String,
+    pub collection: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub k: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RetrieveResponse {
+    pub object: String,
+    pub model: String,
+    pub documents: Vec<crate::executor::vector_store::RetrievedDocument>,
+}